@@ -1,4 +1,7 @@
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 use card::{Card, MAX_RANK, NUM_SUITS, Rank, Suit};
 use deck::Deck;
@@ -9,10 +12,27 @@ use victory_state::VictoryState;
 const NUM_COLUMNS: usize = 9;
 const NUM_SPOTS_IN_HAND: usize = 7;
 
+// Generous enough to cover any solvable deal while still terminating
+// promptly on deals that are not.
+const MAX_SOLVE_DEPTH: usize = 300;
+
+// The branching factor at each state is too high for the depth limit alone
+// to bound runtime, so cap total work too: an unsolvable (or merely very
+// hard) deal gives up rather than searching indefinitely.
+const MAX_SOLVE_NODES: usize = 300_000;
+
+// solve_shallow() runs once per move for a lookahead Strategy, so its
+// budget needs to be much smaller to keep auto-play responsive.
+const MAX_SHALLOW_SOLVE_NODES: usize = 500;
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone)]
 pub struct Board {
     foundations: StaticVec<Foundation, NUM_SUITS>,
     columns: StaticVec<Column, NUM_COLUMNS>,
     hand: StaticVec<SpotInHand, NUM_SPOTS_IN_HAND>,
+    history: Vec<Movement>,
+    redo_stack: Vec<Movement>,
 }
 
 impl Board {
@@ -36,7 +56,15 @@ impl Board {
             spot
         }).collect();
 
-        Self { foundations: foundations, columns: columns, hand: hand }
+        Self { foundations: foundations, columns: columns, hand: hand, history: Vec::new(), redo_stack: Vec::new() }
+    }
+
+    /// Deals a board from a deck shuffled with the given seed, so the same
+    /// seed always produces the same layout.
+    pub fn new_seeded(seed: u64) -> Self {
+        let mut deck = Deck::new();
+        deck.shuffle_seeded(seed);
+        Board::new(Arc::new(Box::new(deck)))
     }
 
     pub fn victory_state(&self) -> VictoryState {
@@ -66,10 +94,50 @@ impl Board {
     }
 
     pub fn execute(&mut self, movement: &Movement) {
+        self.apply(movement);
+        self.redo_stack.clear();
+    }
+
+    fn apply(&mut self, movement: &Movement) {
+        self.apply_unrecorded(movement);
+        self.history.push(*movement);
+    }
+
+    /// As `apply`, but does not record `movement` in `history`. For the
+    /// solver, which clones a fresh `Board` per node and never undoes, so
+    /// there is nothing to gain from keeping history around.
+    fn apply_unrecorded(&mut self, movement: &Movement) {
         let card = self.mut_location_at(movement.origin).give_card();
         self.mut_location_at(movement.destination).receive(card);
     }
 
+    /// Reverses the last executed movement, if there is one. Bypasses
+    /// `permits`, since undoing a move can take a card off a foundation
+    /// (which never gives cards in play) and put it back somewhere it could
+    /// not legally be placed (e.g. a spot in the hand).
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(movement) => {
+                let card = self.mut_location_at(movement.destination).give_card();
+                self.mut_location_at(movement.origin).receive(card);
+                self.redo_stack.push(movement);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Re-applies the last undone movement, if there is one.
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(movement) => {
+                self.apply(&movement);
+                true
+            },
+            None => false,
+        }
+    }
+
     pub fn permits(&self, movement: &Movement) -> bool {
         let origin = self.location_at(movement.origin);
         let destination = self.location_at(movement.destination);
@@ -79,6 +147,15 @@ impl Board {
         }
     }
 
+    /// True if `movement` would take the last card out of its origin
+    /// column, leaving it empty.
+    pub fn gives_last_card(&self, movement: &Movement) -> bool {
+        match movement.origin {
+            'e' ..= 'm' => self.columns[movement.origin as usize - 'e' as usize].cards.len() == 1,
+            _           => false,
+        }
+    }
+
     pub fn permitted_moves(&self) -> Vec<Movement> {
         let mut moves = Vec::new();
         // FIXME Knowledge of the valid ranges is duplicated a lot
@@ -101,6 +178,163 @@ impl Board {
         }
         moves
     }
+
+    /// Searches for a sequence of moves that wins the game from the current
+    /// deal, via iterative-deepening DFS over clones of the board. Returns
+    /// `None` if no win is found within `MAX_SOLVE_DEPTH` moves, or before
+    /// `MAX_SOLVE_NODES` board states have been visited.
+    pub fn solve(&self) -> Option<Vec<Movement>> {
+        self.solve_with_node_count().0
+    }
+
+    /// As `solve`, but also returns the number of board states visited
+    /// across the whole iterative-deepening search, for benchmarking.
+    ///
+    /// `on_path` tracks only the states currently on the DFS stack: a card
+    /// shuffled back and forth between two columns returns to a state two
+    /// plies later, so a transposition table keyed with `depth_remaining`
+    /// would never catch that (the depths never match) and cycles would be
+    /// re-expanded all the way to depth 0. A path-scoped visited set kills
+    /// cycles directly and stays complete within the depth limit, since a
+    /// state is only ever barred from states that would revisit it.
+    pub fn solve_with_node_count(&self) -> (Option<Vec<Movement>>, usize) {
+        let mut nodes_visited = 0;
+        for depth_limit in 1..=MAX_SOLVE_DEPTH {
+            if nodes_visited >= MAX_SOLVE_NODES {
+                break;
+            }
+            let mut on_path = HashSet::new();
+            on_path.insert(self.state_hash());
+            let mut path = Vec::new();
+            if Board::search(self, depth_limit, &mut on_path, &mut path, &mut nodes_visited) {
+                return (Some(path), nodes_visited);
+            }
+        }
+        (None, nodes_visited)
+    }
+
+    fn search(
+        board: &Board,
+        depth_remaining: usize,
+        on_path: &mut HashSet<u64>,
+        path: &mut Vec<Movement>,
+        nodes_visited: &mut usize,
+    ) -> bool {
+        *nodes_visited += 1;
+
+        if board.victory_state() == VictoryState::Won {
+            return true;
+        }
+        if depth_remaining == 0 || *nodes_visited >= MAX_SOLVE_NODES {
+            return false;
+        }
+
+        let mut moves = board.permitted_moves();
+        // Moves onto a foundation are almost always safe, so try them first.
+        moves.sort_by_key(|m| m.destination > 'd');
+
+        for movement in moves {
+            // apply_unrecorded() instead of execute(): the solver never
+            // undoes, so there is no need to pay for history bookkeeping on
+            // every one of the up-to-MAX_SOLVE_NODES clones made here.
+            let mut child = board.clone();
+            child.apply_unrecorded(&movement);
+
+            let hash = child.state_hash();
+            if !on_path.insert(hash) {
+                continue; // already on this path; taking it would be a cycle
+            }
+
+            path.push(movement);
+            if Board::search(&child, depth_remaining - 1, on_path, path, nodes_visited) {
+                return true;
+            }
+            path.pop();
+            on_path.remove(&hash);
+        }
+
+        false
+    }
+
+    /// Searches up to `depth_limit` moves deep and returns the path to the
+    /// best state found, for driving a lookahead `Strategy`. Prefers an
+    /// outright win but otherwise ranks states by how many cards have
+    /// reached the foundations. Returns `None` if there is no legal move.
+    pub fn solve_shallow(&self, depth_limit: usize) -> Option<Vec<Movement>> {
+        let mut seen = HashSet::new();
+        let mut path = Vec::new();
+        let mut best: Option<(u32, Vec<Movement>)> = None;
+        let mut nodes_visited = 0;
+        Board::search_shallow(self, depth_limit, &mut seen, &mut path, &mut best, &mut nodes_visited);
+        best.map(|(_, path)| path)
+    }
+
+    fn search_shallow(
+        board: &Board,
+        depth_remaining: usize,
+        seen: &mut HashSet<u64>,
+        path: &mut Vec<Movement>,
+        best: &mut Option<(u32, Vec<Movement>)>,
+        nodes_visited: &mut usize,
+    ) {
+        *nodes_visited += 1;
+
+        let score = board.foundation_score();
+        if !path.is_empty() && best.as_ref().map_or(true, |&(best_score, _)| score > best_score) {
+            *best = Some((score, path.clone()));
+        }
+
+        if depth_remaining == 0 || *nodes_visited >= MAX_SHALLOW_SOLVE_NODES || board.victory_state() == VictoryState::Won {
+            return;
+        }
+
+        let mut moves = board.permitted_moves();
+        moves.sort_by_key(|m| m.destination > 'd');
+
+        for movement in moves {
+            // apply_unrecorded() instead of execute(): these are throwaway
+            // clones, so there's no reason to pay for history bookkeeping
+            // on every node (see apply_unrecorded's use in search()).
+            let mut child = board.clone();
+            child.apply_unrecorded(&movement);
+
+            if !seen.insert(child.state_hash()) {
+                continue;
+            }
+
+            path.push(movement);
+            Board::search_shallow(&child, depth_remaining - 1, seen, path, best, nodes_visited);
+            path.pop();
+        }
+    }
+
+    fn foundation_score(&self) -> u32 {
+        self.foundations.iter().map(|f| f.top_rank.unwrap_or(0) as u32).sum()
+    }
+
+    fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for foundation in self.foundations.iter() {
+            foundation.top_rank.hash(&mut hasher);
+        }
+        for column in self.columns.iter() {
+            column.cards.hash(&mut hasher);
+        }
+        for spot in self.hand.iter() {
+            spot.card.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_savefile(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_savefile(savefile: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(savefile)
+    }
 }
 
 impl fmt::Display for Board {
@@ -144,6 +378,8 @@ trait Location {
     fn active_card(&self) -> Option<Card>;
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone)]
 struct Foundation {
     suit:     Suit,
     top_rank: Option<Rank>,
@@ -181,7 +417,7 @@ impl Location for Foundation {
         match self.top_rank {
             None       => panic!(),
             Some(rank) => {
-                self.top_rank = Some(rank - 1);
+                self.top_rank = if rank > 1 { Some(rank - 1) } else { None };
                 Card::new(self.suit, rank)
             },
         }
@@ -194,6 +430,8 @@ impl Location for Foundation {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone)]
 struct Column {
     cards: Vec<Card>,
 }
@@ -235,6 +473,8 @@ impl Location for Column {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone)]
 struct SpotInHand {
     card: Option<Card>,
 }
@@ -279,8 +519,77 @@ impl Location for SpotInHand {
     }
 }
 
-#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Movement {
     pub origin: char,
     pub destination: char,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undoing_an_ace_onto_a_foundation_clears_top_rank_instead_of_underflowing() {
+        let mut board = Board {
+            foundations: Suit::iterator().map(|suit| Foundation::new(*suit)).collect(),
+            columns: (1..=NUM_COLUMNS).map(|i| Column::new(i)).collect(),
+            hand: (0..NUM_SPOTS_IN_HAND).map(|_| SpotInHand { card: None }).collect(),
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+        };
+        board.hand[0].card = Some(Card::new(Suit::Spades, 1));
+
+        let movement = Movement { origin: 'n', destination: 'a' };
+        assert!(board.permits(&movement));
+        board.execute(&movement);
+        assert_eq!(board.foundations[0].top_rank, Some(1));
+
+        assert!(board.undo());
+        assert_eq!(board.foundations[0].top_rank, None);
+
+        // Would previously panic: Foundation::give_card left top_rank at
+        // Some(0), and Display-ing Card::new(suit, 0) hits the invalid-rank
+        // panic arm.
+        let _ = board.to_string();
+    }
+
+    #[test]
+    fn solve_finds_a_win_behind_a_reveal_move_despite_an_unrelated_shuffle_cycle() {
+        let mut foundations: StaticVec<Foundation, NUM_SUITS> =
+            Suit::iterator().map(|suit| Foundation::new(*suit)).collect();
+        for foundation in foundations.iter_mut() {
+            foundation.top_rank = Some(if foundation.suit == Suit::Spades { 12 } else { MAX_RANK });
+        }
+
+        let mut columns: StaticVec<Column, NUM_COLUMNS> =
+            (1..=NUM_COLUMNS).map(|i| Column::new(i)).collect();
+        // King of spades is buried under an unrelated card; it must be
+        // moved aside (not directly onto a foundation) before the King can
+        // be played to finish the spades foundation.
+        columns[0].cards = vec![Card::new(Suit::Spades, 13), Card::new(Suit::Diamonds, 4)];
+        columns[1].cards = vec![Card::new(Suit::Spades, 5)];
+        // An unrelated pair of columns that can shuffle a card back and
+        // forth forever, to confirm this doesn't blow the node budget.
+        columns[2].cards = vec![Card::new(Suit::Clubs, 5)];
+        columns[3].cards = vec![Card::new(Suit::Hearts, 6)];
+
+        let board = Board {
+            foundations: foundations,
+            columns: columns,
+            hand: (0..NUM_SPOTS_IN_HAND).map(|_| SpotInHand { card: None }).collect(),
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+        };
+
+        let (solution, nodes_visited) = board.solve_with_node_count();
+        let solution = solution.expect("a win exists just behind a single reveal move");
+        assert_eq!(solution.len(), 2);
+        assert_eq!(solution[0], Movement { origin: 'e', destination: 'f' });
+        assert_eq!(solution[1], Movement { origin: 'e', destination: 'a' });
+
+        // The decoy cycle (g <-> h) must not make the search expensive.
+        assert!(nodes_visited < 1_000, "solve visited {} nodes; cycle pruning may be broken", nodes_visited);
+    }
+}
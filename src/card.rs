@@ -0,0 +1,142 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+pub const NUM_SUITS: usize = 4;
+pub const MAX_RANK: Rank = 13;
+
+pub type Rank = u8;
+
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Color {
+    Black,
+    Red,
+}
+
+#[derive(Debug)]
+pub struct ParseSuitError;
+
+#[derive(Debug)]
+pub struct ParseCardError;
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl Suit {
+    pub fn color(self) -> Color {
+        match self {
+            Suit::Spades | Suit::Clubs    => Color::Black,
+            Suit::Hearts | Suit::Diamonds => Color::Red,
+        }
+    }
+
+    pub fn iterator() -> impl Iterator<Item = &'static Suit> {
+        static SUITS: [Suit; NUM_SUITS] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+        SUITS.iter()
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Suit::Spades   => write!(f, "\u{2660}"),
+            Suit::Hearts   => write!(f, "\u{2661}"),
+            Suit::Diamonds => write!(f, "\u{2662}"),
+            Suit::Clubs    => write!(f, "\u{2663}"),
+        }
+    }
+}
+
+impl TryFrom<char> for Suit {
+    type Error = ParseSuitError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c.to_ascii_uppercase() {
+            'S' => Ok(Suit::Spades),
+            'H' => Ok(Suit::Hearts),
+            'D' => Ok(Suit::Diamonds),
+            'C' => Ok(Suit::Clubs),
+            _   => Err(ParseSuitError),
+        }
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseSuitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Suit::try_from(c),
+            _               => Err(ParseSuitError),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Card {
+    suit: Suit,
+    rank: Rank,
+}
+
+impl Card {
+    pub fn new(suit: Suit, rank: Rank) -> Self {
+        Self { suit: suit, rank: rank }
+    }
+
+    pub fn suit(&self) -> Suit {
+        self.suit
+    }
+
+    pub fn rank(&self) -> Rank {
+        self.rank
+    }
+
+    pub fn color(&self) -> Color {
+        self.suit.color()
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.rank {
+            1        => write!(f, " A{}", self.suit),
+            2 ..= 9  => write!(f, " {}{}", self.rank, self.suit),
+            10       => write!(f, "10{}", self.suit),
+            11       => write!(f, " J{}", self.suit),
+            12       => write!(f, " Q{}", self.suit),
+            13       => write!(f, " K{}", self.suit),
+            _        => panic!(),
+        }
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() < 2 {
+            return Err(ParseCardError);
+        }
+        let (rank_part, suit_part) = s.split_at(s.len() - 1);
+        let suit = Suit::from_str(suit_part).map_err(|_| ParseCardError)?;
+        let rank = match rank_part.to_ascii_uppercase().as_str() {
+            "A" => 1,
+            "J" => 11,
+            "Q" => 12,
+            "K" => 13,
+            r   => r.parse::<Rank>().map_err(|_| ParseCardError)?,
+        };
+        if rank < 1 || rank > MAX_RANK {
+            return Err(ParseCardError);
+        }
+        Ok(Card::new(suit, rank))
+    }
+}
@@ -0,0 +1,36 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use card::{Card, MAX_RANK, Suit};
+
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    pub fn new() -> Self {
+        let mut cards = Vec::new();
+        for suit in Suit::iterator() {
+            for rank in 1..=MAX_RANK {
+                cards.push(Card::new(*suit, rank));
+            }
+        }
+        Self { cards: cards }
+    }
+
+    pub fn shuffle(&mut self) {
+        self.cards.shuffle(&mut thread_rng());
+    }
+
+    /// Shuffles using a seeded PRNG, so the same seed always yields the
+    /// same layout, regardless of platform.
+    pub fn shuffle_seeded(&mut self, seed: u64) {
+        self.cards.shuffle(&mut StdRng::seed_from_u64(seed));
+    }
+
+    pub fn deal(&self, index: usize) -> Card {
+        self.cards[index]
+    }
+}
@@ -1,365 +1,98 @@
 extern crate rand;
-
-use std::fmt;
+extern crate staticvec;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+mod board;
+mod card;
+mod deck;
+mod simulate;
+mod strategy;
+mod victory_state;
+
+use std::env;
 use std::io;
 use std::io::Write;
-use rand::Rng;
 
-#[derive(PartialEq, Clone, Copy)]
-enum Color {
-    Black,
-    Red,
-}
+use board::{Board, Movement};
+use simulate::SolveStats;
+use strategy::{GreedyStrategy, LookaheadStrategy, Strategy};
+use victory_state::VictoryState;
 
-#[derive(PartialEq, Clone, Copy)]
-enum Suit {
-    Spades,
-    Hearts,
-    Diamonds,
-    Clubs,
-}
+const LOOKAHEAD_DEPTH: usize = 4;
 
-impl Suit {
-    fn color(self) -> Color {
-        match self {
-            Suit::Spades | Suit::Clubs    => Color::Black,
-            Suit::Hearts | Suit::Diamonds => Color::Red,
-        }
-    }
-}
+// A greedy strategy has no notion of having "seen" a state before, so
+// nothing stops it shuffling a card back and forth forever; give up
+// rather than let --auto hang on a deal it can't make progress on.
+const MAX_AUTO_MOVES: usize = 2_000;
 
-impl fmt::Display for Suit {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            Suit::Spades   => write!(f, "\u{2660}"),
-            Suit::Hearts   => write!(f, "\u{2661}"),
-            Suit::Diamonds => write!(f, "\u{2662}"),
-            Suit::Clubs    => write!(f, "\u{2663}"),
-        }
+fn strategy_from_name(name: &str) -> Box<dyn Strategy> {
+    match name {
+        "greedy"    => Box::new(GreedyStrategy),
+        "lookahead" => Box::new(LookaheadStrategy::new(LOOKAHEAD_DEPTH)),
+        other       => panic!("Unknown strategy '{}' (expected 'greedy' or 'lookahead')", other),
     }
 }
 
-type Rank = u8;
+fn run_auto(strategy_name: &str, seed: u64) {
+    println!("Seed: {}", seed);
+    let mut board = Board::new_seeded(seed);
+    let mut strategy = strategy_from_name(strategy_name);
 
-#[derive(Copy, Clone)]
-struct Card {
-    pub suit: Suit,
-    pub rank: Rank,
-}
-
-impl Card {
-    fn color(&self) -> Color {
-        self.suit.color()
-    }
-}
-
-impl fmt::Display for Card {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.rank {
-            1       => write!(f, " A{}", self.suit),
-            2 ... 9 => write!(f, " {}{}", self.rank, self.suit),
-            10      => write!(f, "10{}", self.suit),
-            11      => write!(f, " J{}", self.suit),
-            12      => write!(f, " Q{}", self.suit),
-            13      => write!(f, " K{}", self.suit),
-            _       => panic!(),
-        }
-    }
-}
-
-trait Location {
-    fn can_receive(&self, card: &Card) -> bool;
-    fn receive(&mut self, card: Card);
-    fn can_give_card(&self) -> bool;
-    fn give_card(&mut self) -> Card;
-    fn active_card(&self) -> Option<Card>;
-}
-
-struct Foundation {
-    suit:     Suit,
-    top_rank: Option<Rank>,
-}
-
-impl Foundation {
-    fn new(suit: Suit) -> Self {
-        Self { suit: suit, top_rank: None }
-    }
-    fn next_rank(&self) -> Rank {
-        self.top_rank.unwrap_or(0) + 1
-    }
-}
-
-impl fmt::Display for Foundation {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.top_rank {
-          None       => write!(f,"  {}", self.suit),
-          Some(rank) => write!(f, "{}", Card { rank: rank, suit: self.suit }),
-        }
-    }
-}
-
-impl Location for Foundation {
-    fn can_receive(&self, card: &Card) -> bool {
-        (card.suit == self.suit) && (card.rank == self.next_rank())
-    }
-    fn receive(&mut self, card: Card) {
-        self.top_rank = Some(card.rank);
-    }
-    fn can_give_card(&self) -> bool {
-        false
-    }
-    fn give_card(&mut self) -> Card {
-        match self.top_rank {
-            None       => panic!(),
-            Some(rank) => {
-                self.top_rank = Some(rank - 1);
-                Card { suit: self.suit, rank: rank }
+    let mut moves_played = 0;
+    loop {
+        if board.victory_state() == VictoryState::Won {
+            println!("Won in {} moves", moves_played);
+            return;
+        }
+        if moves_played >= MAX_AUTO_MOVES {
+            println!("Gave up after {} moves with no win in sight", moves_played);
+            return;
+        }
+        match strategy.choose_move(&board) {
+            Some(movement) => {
+                board.execute(&movement);
+                moves_played += 1;
             },
-        }
-    }
-    fn active_card(&self) -> Option<Card> {
-        match self.top_rank {
-            None       => None,
-            Some(rank) => Some(Card { suit: self.suit, rank: rank }),
-        }
-    }
-}
-
-struct Column {
-    cards: Vec<Card>,
-}
-
-impl Column {
-    fn new() -> Self {
-        Self { cards: Vec::new() }
-    }
-    fn printable_card_at(&self, i: usize) -> String {
-        match self.cards.get(i) {
-            Some(card) => card.to_string(),
-            None       => String::from("   "),  // TODO bleugh
-        }
-    }
-}
-
-impl Location for Column {
-    fn can_give_card(&self) -> bool {
-        !self.cards.is_empty()
-    }
-    fn give_card(&mut self) -> Card {
-        self.cards.pop().unwrap()
-    }
-    fn can_receive(&self, card: &Card) -> bool {
-        match self.active_card() {
-            Some(active_card) =>
-                (active_card.color() != card.color()) && (active_card.rank == card.rank + 1),
-            None => true
-        }
-    }
-    fn receive(&mut self, card: Card) {
-        self.cards.push(card);
-    }
-    fn active_card(&self) -> Option<Card> {
-        match self.cards.last() {
-            Some(card) => Some(*card),
-            None       => None,
-        }
-    }
-}
-
-struct SpotInHand {
-    card: Option<Card>,
-}
-
-impl SpotInHand {
-    fn new() -> Self {
-        Self { card: None }
-    }
-}
-
-impl fmt::Display for SpotInHand {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.card {
-            None       => write!(f, "   "),
-            Some(card) => write!(f, "{}", card),
-        }
-    }
-}
-
-impl Location for SpotInHand {
-    fn can_give_card(&self) -> bool {
-        self.card.is_some()
-    }
-    fn give_card(&mut self) -> Card {
-        match self.card {
-            Some(c) => {
-                let ret = c.clone();
-                self.card = None;
-                ret
+            None => {
+                println!("Stuck after {} moves", moves_played);
+                return;
             },
-            None => panic!(),
         }
     }
-    fn can_receive(&self, _card: &Card) -> bool {
-        false
-    }
-    fn receive(&mut self, card: Card) {
-        self.card = Some(card);
-    }
-    fn active_card(&self) -> Option<Card> {
-        self.card
-    }
-}
-
-struct Deck {
-    cards: Vec<Card>,
 }
 
-impl Deck {
-    fn new() -> Self {
-        let mut cards = Vec::new();
-        for rank in 1..14 {
-            cards.push(Card { rank: rank, suit: Suit::Spades });
-            cards.push(Card { rank: rank, suit: Suit::Hearts });
-            cards.push(Card { rank: rank, suit: Suit::Diamonds });
-            cards.push(Card { rank: rank, suit: Suit::Clubs });
-        }
-        Deck { cards: cards }
-    }
-    fn shuffle(&mut self) {
-        rand::thread_rng().shuffle(&mut self.cards);
-    }
-    fn deal(&mut self) -> Card {
-        self.cards.pop().unwrap()
+fn seed_from_args(args: &[String]) -> u64 {
+    match args.get(1) {
+        Some(arg) => arg.parse().expect("Seed must be a non-negative integer"),
+        None      => rand::random(),
     }
 }
 
-struct Board {
-    foundations: [Foundation; 4],
-    columns: [Column; 9],
-    hand: [SpotInHand; 7],
-}
+fn print_simulation_report(stats: &SolveStats) {
+    println!("Deals simulated: {}", stats.deals);
+    println!("Solved:          {} ({:.1}%)", stats.solved, 100.0 * stats.win_rate());
 
-impl Board {
-    fn new() -> Board {
-        let mut deck = Deck::new();
-        deck.shuffle();
-
-        let foundations = [
-            Foundation::new(Suit::Spades),
-            Foundation::new(Suit::Hearts),
-            Foundation::new(Suit::Diamonds),
-            Foundation::new(Suit::Clubs),
-        ];
-
-        let mut columns = [
-            Column::new(),
-            Column::new(),
-            Column::new(),
-            Column::new(),
-            Column::new(),
-            Column::new(),
-            Column::new(),
-            Column::new(),
-            Column::new(),
-        ];
-
-        for (i, column) in columns.iter_mut().enumerate() {
-            for _ in 1..(i + 2) {
-                column.receive(deck.deal());
-            }
-        }
-
-        let mut hand = [
-            SpotInHand::new(),
-            SpotInHand::new(),
-            SpotInHand::new(),
-            SpotInHand::new(),
-            SpotInHand::new(),
-            SpotInHand::new(),
-            SpotInHand::new(),
-        ];
-        for spot in hand.iter_mut() {
-          spot.card = Some(deck.deal());
-        }
-
-        Board { foundations: foundations, columns: columns, hand: hand }
+    if !stats.solution_lengths.is_empty() {
+        let min = stats.solution_lengths.iter().min().unwrap();
+        let max = stats.solution_lengths.iter().max().unwrap();
+        let mean = stats.solution_lengths.iter().sum::<usize>() as f64 / stats.solution_lengths.len() as f64;
+        println!("Solution length: min {}, mean {:.1}, max {}", min, mean, max);
     }
 
-    fn mut_location_at(&mut self, label: char) -> &mut Location {
-        match label {
-            'a' ... 'd' => &mut self.foundations[label as usize - 'a' as usize],
-            'e' ... 'm' => &mut self.columns[label as usize - 'e' as usize],
-            'n' ... 't' => &mut self.hand[label as usize - 'n' as usize],
-            _           => panic!("Label outside range"),
-        }
-    }
-
-    fn location_at(&self, label: char) -> &Location {
-        match label {
-            'a' ... 'd' => &self.foundations[label as usize - 'a' as usize],
-            'e' ... 'm' => &self.columns[label as usize - 'e' as usize],
-            'n' ... 't' => &self.hand[label as usize - 'n' as usize],
-            _           => panic!("Label outside range"),
-        }
-    }
-
-    fn execute(&mut self, m: Move) {
-        if !self.permits(m) {
-            panic!("Illegal move");
-        }
-        let card = self.mut_location_at(m.origin).give_card();
-        self.mut_location_at(m.destination).receive(card);
-    }
-
-    fn permits(&self, m: Move) -> bool {
-        let origin = self.location_at(m.origin);
-        let destination = self.location_at(m.destination);
-        match origin.active_card() {
-            Some(card) => origin.can_give_card() && destination.can_receive(&card),
-            None       => false,
-        }
-    }
-}
-
-impl fmt::Display for Board {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let fds = &self.foundations;
-        write!(f, "                           a    b    c    d\n")?;
-        write!(f, "____________________________________________\n")?;
-        write!(f, "                          {}  {}  {}  {}\n\n\n", fds[0], fds[1], fds[2], fds[3])?;
-        write!(f, "  e    f    g    h    i    j    k    l    m\n")?;
-        write!(f, "____________________________________________\n")?;
-
-        let mut i = 0;
-        while !self.columns.iter().all(|c| c.cards.len() < i) {
-            write!(f, "{}  {}  {}  {}  {}  {}  {}  {}  {}\n",
-                self.columns[0].printable_card_at(i),
-                self.columns[1].printable_card_at(i),
-                self.columns[2].printable_card_at(i),
-                self.columns[3].printable_card_at(i),
-                self.columns[4].printable_card_at(i),
-                self.columns[5].printable_card_at(i),
-                self.columns[6].printable_card_at(i),
-                self.columns[7].printable_card_at(i),
-                self.columns[8].printable_card_at(i))?;
-
-            i += 1;
-        }
-
-        write!(f, "\n")?;
-        write!(f, "  n    o    p    q    r    s    t\n")?;
-        write!(f, "____________________________________________\n")?;
-        let h = &self.hand;
-        write!(f, "{}  {}  {}  {}  {}  {}  {}  \n", h[0], h[1], h[2], h[3], h[4], h[5], h[6])
+    if !stats.node_counts.is_empty() {
+        let min = stats.node_counts.iter().min().unwrap();
+        let max = stats.node_counts.iter().max().unwrap();
+        let mean = stats.node_counts.iter().sum::<usize>() as f64 / stats.node_counts.len() as f64;
+        println!("Nodes searched:  min {}, mean {:.1}, max {}", min, mean, max);
     }
 }
 
-#[derive(Copy, Clone)]
-struct Move {
-    origin: char,
-    destination: char,
-}
-
 fn get_char(prompt: &str) -> char {
     loop {
         print!("{}", prompt);
@@ -379,33 +112,81 @@ fn get_char(prompt: &str) -> char {
 }
 
 fn main() {
-    let mut board = Board::new();
+    let args: Vec<String> = env::args().collect();
+
+    if args.get(1).map(String::as_str) == Some("--simulate") {
+        let deals = args.get(2)
+            .expect("--simulate requires a deal count")
+            .parse()
+            .expect("Deal count must be a non-negative integer");
+        let seed = args.get(3)
+            .map(|s| s.parse().expect("Seed must be a non-negative integer"))
+            .unwrap_or_else(rand::random);
+
+        print_simulation_report(&simulate::estimate_win_rate(deals, seed));
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("--auto") {
+        let strategy_name = args.get(2).expect("--auto requires a strategy name (greedy or lookahead)");
+        let seed = args.get(3)
+            .map(|s| s.parse().expect("Seed must be a non-negative integer"))
+            .unwrap_or_else(rand::random);
+
+        run_auto(strategy_name, seed);
+        return;
+    }
+
+    let seed = seed_from_args(&args);
+    println!("Seed: {}", seed);
+    let mut board = Board::new_seeded(seed);
+
     let clear_screen = "\x1b[2J\x1b[1;1H";
     println!("{}\n{}", clear_screen, board);
 
     loop {
-        let mut m = Move { origin: 'a', destination: 'a' }; // dummy
+        let mut movement = Movement { origin: 'a', destination: 'a' }; // dummy
+        let mut undone_or_redone = false;
 
         loop {
-            let c = get_char("\nEnter position to move FROM (labelled e-t): ");
+            let c = get_char("\nEnter position to move FROM (labelled e-t), or U/R to undo/redo: ");
+            if c == 'U' {
+                if !board.undo() {
+                    println!("Nothing to undo");
+                }
+                undone_or_redone = true;
+                break;
+            }
+            if c == 'R' {
+                if !board.redo() {
+                    println!("Nothing to redo");
+                }
+                undone_or_redone = true;
+                break;
+            }
             if c >= 'e' && c <= 't' {
-                m.origin = c;
+                movement.origin = c;
                 break;
             }
-            println!("You must enter a letter from e to t");
+            println!("You must enter a letter from e to t, or U/R to undo/redo");
+        }
+
+        if undone_or_redone {
+            println!("{}\n{}", clear_screen, board);
+            continue;
         }
 
         loop {
             let c = get_char("\nEnter position to move TO (labelled a-m): ");
             if c >= 'a' && c <= 'm' {
-                m.destination = c;
+                movement.destination = c;
                 break;
             }
             println!("You must enter a letter from a to m");
         }
 
-        if board.permits(m) {
-            board.execute(m);
+        if board.permits(&movement) {
+            board.execute(&movement);
             println!("{}\n{}", clear_screen, board);
         } else {
             println!("That move is not permitted, try again!");
@@ -0,0 +1,61 @@
+use std::thread;
+
+use board::Board;
+
+pub struct SolveStats {
+    pub deals: usize,
+    pub solved: usize,
+    pub solution_lengths: Vec<usize>,
+    pub node_counts: Vec<usize>,
+}
+
+impl SolveStats {
+    pub fn win_rate(&self) -> f64 {
+        self.solved as f64 / self.deals as f64
+    }
+}
+
+/// Generates `deals` seeded deals (seeded from `seed`, `seed + 1`, ...),
+/// solves each in parallel across threads, and reports the fraction that
+/// are solvable plus the distribution of solution lengths and per-deal
+/// node counts.
+///
+/// "Solvable" here means `Board::solve` found a win within its own
+/// `MAX_SOLVE_DEPTH`/`MAX_SOLVE_NODES` budget; an unsolved deal may still
+/// be winnable by a search given more budget, so `win_rate` is a lower
+/// bound on true solvability, not an exact figure.
+pub fn estimate_win_rate(deals: usize, seed: u64) -> SolveStats {
+    if deals == 0 {
+        return SolveStats { deals: 0, solved: 0, solution_lengths: Vec::new(), node_counts: Vec::new() };
+    }
+
+    let num_threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1).min(deals);
+    let chunk_size = (deals + num_threads - 1) / num_threads;
+
+    let handles: Vec<_> = (0..deals).step_by(chunk_size).map(|chunk_start| {
+        let chunk_end = (chunk_start + chunk_size).min(deals);
+        thread::spawn(move || {
+            (chunk_start..chunk_end).map(|i| {
+                let board = Board::new_seeded(seed.wrapping_add(i as u64));
+                let (solution, nodes_visited) = board.solve_with_node_count();
+                (solution.map(|path| path.len()), nodes_visited)
+            }).collect::<Vec<_>>()
+        })
+    }).collect();
+
+    let mut solved = 0;
+    let mut solution_lengths = Vec::new();
+    let mut node_counts = Vec::new();
+
+    for handle in handles {
+        for (solution_length, nodes_visited) in handle.join().expect("simulation thread panicked") {
+            node_counts.push(nodes_visited);
+            if let Some(length) = solution_length {
+                solved += 1;
+                solution_lengths.push(length);
+            }
+        }
+    }
+
+    SolveStats { deals: deals, solved: solved, solution_lengths: solution_lengths, node_counts: node_counts }
+}
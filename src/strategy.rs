@@ -0,0 +1,39 @@
+use board::{Board, Movement};
+
+/// Something that can pick a move for the current deal, so a game can be
+/// driven automatically instead of from stdin.
+pub trait Strategy {
+    fn choose_move(&mut self, board: &Board) -> Option<Movement>;
+}
+
+/// Always advances a foundation when possible, otherwise prefers a move
+/// that empties a column, otherwise takes whatever move is available.
+pub struct GreedyStrategy;
+
+impl Strategy for GreedyStrategy {
+    fn choose_move(&mut self, board: &Board) -> Option<Movement> {
+        let moves = board.permitted_moves();
+
+        moves.iter().find(|m| m.destination <= 'd').cloned()
+            .or_else(|| moves.iter().find(|m| board.gives_last_card(m)).cloned())
+            .or_else(|| moves.first().cloned())
+    }
+}
+
+/// Calls `Board::solve_shallow` to a fixed depth and plays the first move
+/// of the best path found.
+pub struct LookaheadStrategy {
+    depth: usize,
+}
+
+impl LookaheadStrategy {
+    pub fn new(depth: usize) -> Self {
+        Self { depth: depth }
+    }
+}
+
+impl Strategy for LookaheadStrategy {
+    fn choose_move(&mut self, board: &Board) -> Option<Movement> {
+        board.solve_shallow(self.depth).and_then(|path| path.into_iter().next())
+    }
+}
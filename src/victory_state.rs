@@ -0,0 +1,5 @@
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum VictoryState {
+    Won,
+    Ongoing,
+}